@@ -0,0 +1,119 @@
+use flate2::read::GzDecoder;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::io::Result as IoResult;
+
+use {EventReader, Result, starts_with_magic};
+
+/// The input to an `EventReader` returned by `parse_maybe_gzipped`.
+///
+/// Gzip streams cannot be seeked so compressed input is decompressed into memory up front.
+pub enum MaybeGzipped<R> {
+    Plain(R),
+    Gzipped(Cursor<Vec<u8>>),
+}
+
+impl<R: Read> Read for MaybeGzipped<R> {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match *self {
+            MaybeGzipped::Plain(ref mut reader) => reader.read(buf),
+            MaybeGzipped::Gzipped(ref mut reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<R: Seek> Seek for MaybeGzipped<R> {
+    fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+        match *self {
+            MaybeGzipped::Plain(ref mut reader) => reader.seek(pos),
+            MaybeGzipped::Gzipped(ref mut reader) => reader.seek(pos),
+        }
+    }
+}
+
+/// Creates an `EventReader` for a plist which may be gzip compressed.
+///
+/// If the input starts with the gzip magic bytes it is decompressed before the plist format is
+/// detected, otherwise it is read as is.
+pub fn parse_maybe_gzipped<R: Read + Seek>(mut reader: R) -> Result<EventReader<MaybeGzipped<R>>> {
+    if !try!(starts_with_magic(&mut reader, &[0x1f, 0x8b])) {
+        return Ok(EventReader::new(MaybeGzipped::Plain(reader)));
+    }
+
+    let mut decoder = try!(GzDecoder::new(reader));
+    let mut data = Vec::new();
+    try!(decoder.read_to_end(&mut data));
+
+    Ok(EventReader::new(MaybeGzipped::Gzipped(Cursor::new(data))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use std::io::Result as IoResult;
+    use std::path::Path;
+
+    use super::*;
+    use {EventReader, PlistEvent};
+
+    #[test]
+    fn gzipped_xml() {
+        let reader = File::open(&Path::new("./tests/data/xml.plist.gz")).unwrap();
+        let events: Vec<PlistEvent> = parse_maybe_gzipped(reader)
+                                          .unwrap()
+                                          .map(|e| e.unwrap())
+                                          .collect();
+
+        let reader = File::open(&Path::new("./tests/data/xml.plist")).unwrap();
+        let comparison: Vec<PlistEvent> = EventReader::new(reader).map(|e| e.unwrap()).collect();
+
+        assert_eq!(events, comparison);
+    }
+
+    /// Returns at most one byte from each call to `read`.
+    struct ByteAtATime<R>(R);
+
+    impl<R: Read> Read for ByteAtATime<R> {
+        fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+            let len = ::std::cmp::min(buf.len(), 1);
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    impl<R: Seek> Seek for ByteAtATime<R> {
+        fn seek(&mut self, pos: SeekFrom) -> IoResult<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    #[test]
+    fn gzipped_xml_short_reads() {
+        let mut file = File::open(&Path::new("./tests/data/xml.plist.gz")).unwrap();
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).unwrap();
+        let reader = ByteAtATime(Cursor::new(data));
+        let events: Vec<PlistEvent> = parse_maybe_gzipped(reader)
+                                          .unwrap()
+                                          .map(|e| e.unwrap())
+                                          .collect();
+
+        let reader = File::open(&Path::new("./tests/data/xml.plist")).unwrap();
+        let comparison: Vec<PlistEvent> = EventReader::new(reader).map(|e| e.unwrap()).collect();
+
+        assert_eq!(events, comparison);
+    }
+
+    #[test]
+    fn plain_passthrough() {
+        let reader = File::open(&Path::new("./tests/data/binary.plist")).unwrap();
+        let events: Vec<PlistEvent> = parse_maybe_gzipped(reader)
+                                          .unwrap()
+                                          .map(|e| e.unwrap())
+                                          .collect();
+
+        let reader = File::open(&Path::new("./tests/data/binary.plist")).unwrap();
+        let comparison: Vec<PlistEvent> = EventReader::new(reader).map(|e| e.unwrap()).collect();
+
+        assert_eq!(events, comparison);
+    }
+}
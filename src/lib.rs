@@ -40,6 +40,8 @@ extern crate chrono;
 extern crate rustc_serialize;
 extern crate serde;
 extern crate xml as xml_rs;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 
 pub mod binary;
 pub mod xml;
@@ -47,15 +49,22 @@ pub mod xml;
 mod builder;
 mod de;
 mod ser;
+mod validator;
+#[cfg(feature = "gzip")]
+mod gzip;
 
 pub use de::Deserializer;
+#[cfg(feature = "gzip")]
+pub use gzip::{parse_maybe_gzipped, MaybeGzipped};
 pub use ser::Serializer;
+pub use validator::{validate, Validator};
 
 use chrono::{DateTime, UTC};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 use std::fmt;
 use std::io::{Read, Seek, SeekFrom, Write};
+use std::ops::{Index, IndexMut};
 use std::io::Error as IoError;
 
 pub fn deserialize<R: Read + Seek, T: Deserialize>(reader: R) -> Result<T> {
@@ -267,6 +276,69 @@ impl Plist {
     }
 }
 
+impl<'a> Index<&'a str> for Plist {
+    type Output = Plist;
+
+    /// Returns the value for `key` in a Dictionary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Plist` is not a Dictionary or if `key` is not present. Use `as_dictionary`
+    /// for a non-panicking lookup.
+    fn index(&self, key: &'a str) -> &Plist {
+        match self.as_dictionary() {
+            Some(dict) => &dict[key],
+            None => panic!("cannot index a non-dictionary plist with a string key"),
+        }
+    }
+}
+
+impl<'a> IndexMut<&'a str> for Plist {
+    /// Returns the mutable value for `key` in a Dictionary.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Plist` is not a Dictionary or if `key` is not present. New keys must be
+    /// added with `as_dictionary_mut`.
+    fn index_mut(&mut self, key: &'a str) -> &mut Plist {
+        match self.as_dictionary_mut() {
+            Some(dict) => dict.get_mut(key).expect("key not present in dictionary plist"),
+            None => panic!("cannot index a non-dictionary plist with a string key"),
+        }
+    }
+}
+
+impl Index<usize> for Plist {
+    type Output = Plist;
+
+    /// Returns the element at `index` in an Array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Plist` is not an Array or if `index` is out of bounds. Use `as_array` for a
+    /// non-panicking lookup.
+    fn index(&self, index: usize) -> &Plist {
+        match self.as_array() {
+            Some(array) => &array[index],
+            None => panic!("cannot index a non-array plist with an integer"),
+        }
+    }
+}
+
+impl IndexMut<usize> for Plist {
+    /// Returns the mutable element at `index` in an Array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Plist` is not an Array or if `index` is out of bounds.
+    fn index_mut(&mut self, index: usize) -> &mut Plist {
+        match self.as_array_mut() {
+            Some(array) => &mut array[index],
+            None => panic!("cannot index a non-array plist with an integer"),
+        }
+    }
+}
+
 /// An encoding of a plist as a flat structure.
 ///
 /// Output by the event readers.
@@ -304,6 +376,8 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 #[derive(Debug)]
 pub enum Error {
     InvalidData,
+    /// An integer was well formed but too large to be represented.
+    IntegerOverflow,
     UnexpectedEof,
     Io(IoError),
     Serde(String)
@@ -313,6 +387,7 @@ impl ::std::error::Error for Error {
     fn description(&self) -> &str {
         match *self {
             Error::InvalidData => "invalid data",
+            Error::IntegerOverflow => "integer overflow",
             Error::UnexpectedEof => "unexpected eof",
             Error::Io(ref err) => err.description(),
             Error::Serde(ref err) => &err
@@ -356,17 +431,27 @@ impl<R: Read + Seek> EventReader<R> {
     }
 
     fn is_binary(reader: &mut R) -> Result<bool> {
-        try!(reader.seek(SeekFrom::Start(0)));
-        let mut magic = [0; 8];
-        try!(reader.read(&mut magic));
-        try!(reader.seek(SeekFrom::Start(0)));
+        starts_with_magic(reader, b"bplist00")
+    }
+}
+
+/// Returns true if the reader starts with `magic`, leaving it positioned at the start.
+fn starts_with_magic<R: Read + Seek>(reader: &mut R, magic: &[u8]) -> Result<bool> {
+    try!(reader.seek(SeekFrom::Start(0)));
+    let mut data = vec![0; magic.len()];
+    let mut total_read = 0;
 
-        Ok(if &magic == b"bplist00" {
-            true
-        } else {
-            false
-        })
+    while total_read < data.len() {
+        let read = try!(reader.read(&mut data[total_read..]));
+        if read == 0 {
+            break;
+        }
+        total_read += read;
     }
+
+    try!(reader.seek(SeekFrom::Start(0)));
+
+    Ok(total_read == magic.len() && &data[..] == magic)
 }
 
 impl<R: Read + Seek> Iterator for EventReader<R> {
@@ -447,4 +532,37 @@ mod tests {
         assert_eq!(Plist::String("2".to_owned()).as_string(), Some("2"));
         assert_eq!(Plist::String("t".to_owned()).into_string(), Some("t".to_owned()));
     }
+
+    #[test]
+    fn test_plist_index() {
+        use std::fs::File;
+        use std::path::Path;
+
+        let reader = File::open(&Path::new("./tests/data/xml.plist")).unwrap();
+        let mut plist = Plist::read(reader).unwrap();
+
+        assert_eq!(plist["Lines"][0],
+                   Plist::String("It is a tale told by an idiot,".to_owned()));
+        assert_eq!(plist["Death"], Plist::Integer(1564));
+
+        plist["Death"] = Plist::Integer(1616);
+        assert_eq!(plist["Death"], Plist::Integer(1616));
+
+        plist["Lines"][1] = Plist::Boolean(true);
+        assert_eq!(plist["Lines"][1], Plist::Boolean(true));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_plist_index_missing_key() {
+        let plist = Plist::Dictionary(::std::collections::BTreeMap::new());
+        let _ = &plist["missing"];
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_plist_index_not_array() {
+        let plist = Plist::Integer(0);
+        let _ = &plist[0];
+    }
 }
@@ -0,0 +1,270 @@
+use {Error, PlistEvent, Result};
+
+enum Element {
+    Dictionary(DictionaryState),
+    Array,
+    Root,
+}
+
+enum DictionaryState {
+    ExpectKey,
+    ExpectValue,
+}
+
+/// How an event fits into the plist being built.
+pub struct Transition {
+    /// The event is the first of the plist.
+    pub starts_plist: bool,
+    /// The event is a dictionary key rather than a value.
+    pub is_key: bool,
+    /// The event completes the plist.
+    pub ends_plist: bool,
+}
+
+/// Tracks the open containers of a plist as its events are seen.
+///
+/// Shared by the `Validator` and the XML event writer so they agree on which streams are valid.
+pub struct EventStack {
+    stack: Vec<Element>,
+    complete: bool,
+}
+
+impl EventStack {
+    pub fn new() -> EventStack {
+        EventStack {
+            stack: Vec::new(),
+            complete: false,
+        }
+    }
+
+    /// Returns true if a whole plist has been seen.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Checks that `event` may appear next in the plist and records it.
+    ///
+    /// The state is left unchanged if an error is returned.
+    pub fn push(&mut self, event: &PlistEvent) -> Result<Transition> {
+        if self.complete {
+            // Only a single root value is allowed
+            return Err(Error::InvalidData);
+        }
+
+        let starts_plist = self.stack.is_empty();
+        let expect_key = match self.stack.last() {
+            Some(&Element::Dictionary(DictionaryState::ExpectKey)) => true,
+            _ => false,
+        };
+
+        let is_key = match *event {
+            PlistEvent::EndArray => {
+                if let Some(&Element::Array) = self.stack.last() {} else {
+                    return Err(Error::InvalidData);
+                }
+                self.stack.pop();
+                false
+            }
+            PlistEvent::EndDictionary => {
+                if !expect_key {
+                    return Err(Error::InvalidData);
+                }
+                self.stack.pop();
+                false
+            }
+            PlistEvent::StringValue(_) if expect_key => {
+                self.set_dictionary_state(DictionaryState::ExpectValue);
+                true
+            }
+            _ if expect_key => return Err(Error::InvalidData),
+            _ => {
+                if starts_plist {
+                    self.stack.push(Element::Root);
+                } else {
+                    // A dictionary value is followed by another key
+                    self.set_dictionary_state(DictionaryState::ExpectKey);
+                }
+
+                match *event {
+                    PlistEvent::StartArray(_) => self.stack.push(Element::Array),
+                    PlistEvent::StartDictionary(_) => {
+                        self.stack.push(Element::Dictionary(DictionaryState::ExpectKey))
+                    }
+                    _ => (),
+                }
+                false
+            }
+        };
+
+        // Only the root element is left so the plist is complete
+        let ends_plist = self.stack.len() == 1;
+        if ends_plist {
+            self.stack.pop();
+            self.complete = true;
+        }
+
+        Ok(Transition {
+            starts_plist: starts_plist,
+            is_key: is_key,
+            ends_plist: ends_plist,
+        })
+    }
+
+    fn set_dictionary_state(&mut self, new_state: DictionaryState) {
+        if let Some(&mut Element::Dictionary(ref mut state)) = self.stack.last_mut() {
+            *state = new_state;
+        }
+    }
+}
+
+/// Checks that a stream of events describes a single well formed plist.
+///
+/// Created by the `validate` function.
+pub struct Validator<I> {
+    events: I,
+    stack: EventStack,
+    finished: bool,
+}
+
+/// Wraps an iterator of events, passing them through unchanged until the first event which
+/// cannot appear at its position in a plist. An error is returned in place of that event, or
+/// after the last event if the plist is incomplete, and the iterator then ends.
+pub fn validate<I>(events: I) -> Validator<I::IntoIter>
+    where I: IntoIterator<Item = PlistEvent>
+{
+    Validator {
+        events: events.into_iter(),
+        stack: EventStack::new(),
+        finished: false,
+    }
+}
+
+impl<I: Iterator<Item = PlistEvent>> Iterator for Validator<I> {
+    type Item = Result<PlistEvent>;
+
+    fn next(&mut self) -> Option<Result<PlistEvent>> {
+        if self.finished {
+            return None;
+        }
+
+        let result = match self.events.next() {
+            Some(event) => self.stack.push(&event).map(|_| event),
+            None if self.stack.is_complete() => {
+                self.finished = true;
+                return None;
+            }
+            None => Err(Error::UnexpectedEof),
+        };
+
+        if result.is_err() {
+            self.finished = true;
+        }
+
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PlistEvent;
+
+    #[test]
+    fn valid_stream() {
+        use PlistEvent::*;
+
+        let events = vec![StartDictionary(None),
+                          StringValue("Author".to_owned()),
+                          StringValue("William Shakespeare".to_owned()),
+                          StringValue("Lines".to_owned()),
+                          StartArray(None),
+                          StringValue("It is a tale told by an idiot,".to_owned()),
+                          StartDictionary(None),
+                          EndDictionary,
+                          EndArray,
+                          StringValue("Death".to_owned()),
+                          IntegerValue(1564),
+                          EndDictionary];
+
+        let validated: Vec<PlistEvent> = validate(events.clone()).map(|e| e.unwrap()).collect();
+
+        assert_eq!(validated, events);
+    }
+
+    #[test]
+    fn unbalanced_stream() {
+        use PlistEvent::*;
+
+        let events = vec![StartDictionary(None),
+                          StringValue("Lines".to_owned()),
+                          StartArray(None),
+                          StringValue("It is a tale told by an idiot,".to_owned()),
+                          EndDictionary,
+                          EndDictionary];
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 5);
+        assert!(validated[..4].iter().all(|e| e.is_ok()));
+        assert!(validated[4].is_err());
+    }
+
+    #[test]
+    fn value_in_key_position() {
+        use PlistEvent::*;
+
+        let events = vec![StartDictionary(None), IntegerValue(1564), EndDictionary];
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 2);
+        assert!(validated[1].is_err());
+    }
+
+    #[test]
+    fn mismatched_end() {
+        use PlistEvent::*;
+
+        let events = vec![StartArray(None), EndDictionary];
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 2);
+        assert!(validated[1].is_err());
+    }
+
+    #[test]
+    fn unexpected_eof() {
+        use PlistEvent::*;
+
+        let events = vec![StartArray(None), BooleanValue(true)];
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 3);
+        assert!(validated[2].is_err());
+    }
+
+    #[test]
+    fn empty_stream() {
+        let events: Vec<PlistEvent> = Vec::new();
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 1);
+        assert!(validated[0].is_err());
+    }
+
+    #[test]
+    fn multiple_roots() {
+        use PlistEvent::*;
+
+        let events = vec![IntegerValue(1564), IntegerValue(1616)];
+
+        let validated: Vec<_> = validate(events).collect();
+
+        assert_eq!(validated.len(), 2);
+        assert!(validated[0].is_ok());
+        assert!(validated[1].is_err());
+    }
+}
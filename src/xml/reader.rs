@@ -92,12 +92,18 @@ impl<R: Read> EventReader<R> {
                             return Some(self.read_content(|s| {
                                 match FromStr::from_str(&s) {
                                     Ok(i) => Ok(PlistEvent::IntegerValue(i)),
+                                    // ParseIntError does not expose why parsing failed
+                                    Err(_) if is_integer_literal(&s) => {
+                                        Err(Error::IntegerOverflow)
+                                    }
                                     Err(_) => Err(Error::InvalidData),
                                 }
                             }))
                         }
                         "real" => {
                             return Some(self.read_content(|s| {
+                                // Reals never overflow as f64::from_str saturates to infinity so
+                                // any failure is malformed data
                                 match FromStr::from_str(&s) {
                                     Ok(f) => Ok(PlistEvent::RealValue(f)),
                                     Err(_) => Err(Error::InvalidData),
@@ -138,6 +144,16 @@ impl<R: Read> EventReader<R> {
     }
 }
 
+/// Returns true if `s` has the syntax of an integer, regardless of its magnitude.
+fn is_integer_literal(s: &str) -> bool {
+    let digits = if s.starts_with('-') || s.starts_with('+') {
+        &s[1..]
+    } else {
+        s
+    };
+    !digits.is_empty() && digits.bytes().all(|b| b >= b'0' && b <= b'9')
+}
+
 impl<R: Read> Iterator for EventReader<R> {
     type Item = Result<PlistEvent>;
 
@@ -164,10 +180,11 @@ impl<R: Read> Iterator for EventReader<R> {
 mod tests {
     use chrono::{TimeZone, UTC};
     use std::fs::File;
+    use std::io::Cursor;
     use std::path::Path;
 
     use super::*;
-    use PlistEvent;
+    use {Error, PlistEvent};
 
     #[test]
     fn streaming_parser() {
@@ -208,4 +225,45 @@ mod tests {
 
         assert!(events.last().unwrap().is_err());
     }
+
+    fn read_integer(s: &str) -> Option<::Result<PlistEvent>> {
+        let xml = format!("<plist version=\"1.0\"><integer>{}</integer></plist>", s);
+        let mut streaming_parser = EventReader::new(Cursor::new(xml.into_bytes()));
+        streaming_parser.next()
+    }
+
+    #[test]
+    fn integer_overflow() {
+        match read_integer("9223372036854775808") {
+            Some(Err(Error::IntegerOverflow)) => (),
+            other => panic!("expected IntegerOverflow, got {:?}", other),
+        }
+        match read_integer("-9223372036854775809") {
+            Some(Err(Error::IntegerOverflow)) => (),
+            other => panic!("expected IntegerOverflow, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn malformed_integer() {
+        match read_integer("12ab") {
+            Some(Err(Error::InvalidData)) => (),
+            other => panic!("expected InvalidData, got {:?}", other),
+        }
+        match read_integer("-") {
+            Some(Err(Error::InvalidData)) => (),
+            other => panic!("expected InvalidData, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn large_real() {
+        let xml = "<plist version=\"1.0\"><real>1e400</real></plist>";
+        let mut streaming_parser = EventReader::new(Cursor::new(xml.as_bytes()));
+
+        match streaming_parser.next() {
+            Some(Ok(PlistEvent::RealValue(f))) if f.is_infinite() => (),
+            other => panic!("expected infinite RealValue, got {:?}", other),
+        }
+    }
 }
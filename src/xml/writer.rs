@@ -8,6 +8,7 @@ use xml_rs::writer::{Error as XmlWriterError, EventWriter as XmlEventWriter, Emi
 use xml_rs::writer::events::XmlEvent as WriteXmlEvent;
 
 use {Error, EventWriter as PlistEventWriter, PlistEvent, Result};
+use validator::EventStack;
 
 impl From<XmlWriterError> for Error {
     fn from(err: XmlWriterError) -> Error {
@@ -18,20 +19,9 @@ impl From<XmlWriterError> for Error {
     }
 }
 
-enum Element {
-    Dictionary(DictionaryState),
-    Array,
-    Root,
-}
-
-enum DictionaryState {
-    ExpectKey,
-    ExpectValue,
-}
-
 pub struct EventWriter<W: Write> {
     xml_writer: XmlEventWriter<W>,
-    stack: Vec<Element>,
+    stack: EventStack,
     // Not very nice
     empty_namespace: Namespace,
 }
@@ -52,7 +42,7 @@ impl<W: Write> EventWriter<W> {
 
         EventWriter {
             xml_writer: XmlEventWriter::new_with_config(writer, config),
-            stack: Vec::new(),
+            stack: EventStack::new(),
             empty_namespace: Namespace::empty(),
         }
     }
@@ -83,75 +73,48 @@ impl<W: Write> EventWriter<W> {
         Ok(())
     }
 
-    fn maybe_end_plist(&mut self) -> Result<()> {
-        // If there are no more open tags then write the </plist> element
-        if self.stack.len() == 1 {
-            try!(self.end_element("plist"));
-            if let Some(Element::Root) = self.stack.pop() {} else {
-                return Err(Error::InvalidData);
-            }
-        }
-        Ok(())
-    }
-
     pub fn write(&mut self, event: &PlistEvent) -> Result<()> {
         <Self as PlistEventWriter>::write(self, event)
     }
 }
 
+fn is_illegal_xml_char(c: char) -> bool {
+    match c {
+        '\u{9}' | '\u{a}' | '\u{d}' => false,
+        '\u{0}'...'\u{1f}' | '\u{fffe}' | '\u{ffff}' => true,
+        _ => false,
+    }
+}
+
 impl<W: Write> PlistEventWriter for EventWriter<W> {
     fn write(&mut self, event: &PlistEvent) -> Result<()> {
-        match self.stack.pop() {
-            Some(Element::Dictionary(DictionaryState::ExpectKey)) => {
-                match *event {
-                    PlistEvent::StringValue(ref value) => {
-                        try!(self.write_element_and_value("key", &*value));
-                        self.stack.push(Element::Dictionary(DictionaryState::ExpectValue));
-                    }
-                    PlistEvent::EndDictionary => {
-                        try!(self.end_element("dict"));
-                        // We might be closing the last tag here as well
-                        try!(self.maybe_end_plist());
-                    }
-                    _ => return Err(Error::InvalidData),
-                };
-                return Ok(());
-            }
-            Some(Element::Dictionary(DictionaryState::ExpectValue)) => {
-                self.stack.push(Element::Dictionary(DictionaryState::ExpectKey))
-            }
-            Some(other) => self.stack.push(other),
-            None => {
-                let version_name = Name::local("version");
-                let version_attr = Attribute::new(version_name, "1.0");
-
-                try!(self.xml_writer.write(WriteXmlEvent::StartElement {
-                    name: Name::local("plist"),
-                    attributes: Cow::Borrowed(&[version_attr]),
-                    namespace: Cow::Borrowed(&self.empty_namespace),
-                }));
-
-                self.stack.push(Element::Root);
+        // XML 1.0 cannot represent these characters even when escaped so reject them before
+        // anything is written
+        if let PlistEvent::StringValue(ref value) = *event {
+            if value.chars().any(is_illegal_xml_char) {
+                return Err(Error::InvalidData);
             }
         }
 
+        let transition = try!(self.stack.push(event));
+
+        if transition.starts_plist {
+            let version_name = Name::local("version");
+            let version_attr = Attribute::new(version_name, "1.0");
+
+            try!(self.xml_writer.write(WriteXmlEvent::StartElement {
+                name: Name::local("plist"),
+                attributes: Cow::Borrowed(&[version_attr]),
+                namespace: Cow::Borrowed(&self.empty_namespace),
+            }));
+        }
+
         match *event {
-            PlistEvent::StartArray(_) => {
-                try!(self.start_element("array"));
-                self.stack.push(Element::Array);
-            }
-            PlistEvent::EndArray => {
-                try!(self.end_element("array"));
-                if let Some(Element::Array) = self.stack.pop() {} else {
-                    return Err(Error::InvalidData);
-                }
-            }
+            PlistEvent::StartArray(_) => try!(self.start_element("array")),
+            PlistEvent::EndArray => try!(self.end_element("array")),
 
-            PlistEvent::StartDictionary(_) => {
-                try!(self.start_element("dict"));
-                self.stack.push(Element::Dictionary(DictionaryState::ExpectKey));
-            }
-            PlistEvent::EndDictionary => return Err(Error::InvalidData),
+            PlistEvent::StartDictionary(_) => try!(self.start_element("dict")),
+            PlistEvent::EndDictionary => try!(self.end_element("dict")),
 
             PlistEvent::BooleanValue(true) => {
                 try!(self.start_element("true"));
@@ -175,12 +138,18 @@ impl<W: Write> PlistEventWriter for EventWriter<W> {
             PlistEvent::RealValue(ref value) => {
                 try!(self.write_element_and_value("real", &value.to_string()))
             }
+            PlistEvent::StringValue(ref value) if transition.is_key => {
+                try!(self.write_element_and_value("key", &*value))
+            }
             PlistEvent::StringValue(ref value) => {
                 try!(self.write_element_and_value("string", &*value))
             }
         };
 
-        try!(self.maybe_end_plist());
+        // If there are no more open tags then write the </plist> element
+        if transition.ends_plist {
+            try!(self.end_element("plist"));
+        }
 
         Ok(())
     }
@@ -189,9 +158,13 @@ impl<W: Write> PlistEventWriter for EventWriter<W> {
 #[cfg(test)]
 mod tests {
     use chrono::{TimeZone, UTC};
-    use std::io::Cursor;
+    use std::cell::RefCell;
+    use std::io::{Cursor, Write};
+    use std::io::Result as IoResult;
+    use std::rc::Rc;
 
     use super::*;
+    use Error;
 
     #[test]
     fn streaming_parser() {
@@ -250,4 +223,49 @@ mod tests {
 
         assert_eq!(s, comparison);
     }
+
+    /// A sink which can be inspected while an `EventWriter` is writing to it.
+    #[derive(Clone)]
+    struct SharedBuffer(Rc<RefCell<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            self.0.borrow_mut().write(buf)
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn illegal_control_character() {
+        use PlistEvent::*;
+
+        let buffer = SharedBuffer(Rc::new(RefCell::new(Vec::new())));
+        let mut plist_w = EventWriter::new(buffer.clone());
+
+        plist_w.write(&StartArray(None)).unwrap();
+        let written = buffer.0.borrow().clone();
+
+        match plist_w.write(&StringValue("null \u{0} byte".to_owned())) {
+            Err(Error::InvalidData) => (),
+            other => panic!("expected InvalidData, got {:?}", other),
+        }
+        assert_eq!(*buffer.0.borrow(), written);
+        assert!(!String::from_utf8(written).unwrap().contains("<string"));
+    }
+
+    #[test]
+    fn illegal_control_character_in_key() {
+        use PlistEvent::*;
+
+        let mut plist_w = EventWriter::new(Cursor::new(Vec::new()));
+
+        plist_w.write(&StartDictionary(None)).unwrap();
+        match plist_w.write(&StringValue("\u{1}".to_owned())) {
+            Err(Error::InvalidData) => (),
+            other => panic!("expected InvalidData, got {:?}", other),
+        }
+    }
 }